@@ -1,30 +1,46 @@
-use super::{trap, Error, Signal};
+use super::{trap, Error, Response, Signal, Trap, TrapGuard};
 use crossbeam_channel as xchan;
 use futures::stream::Stream;
-use futures::task::AtomicTask;
-use futures::{Async, Poll};
+use futures::task::AtomicWaker;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::task::{Context, Poll};
 
-/// An asynchronous stream of [Signal]s generated by [trap_stream].
+/// An asynchronous stream of [Signal]s generated by [trap_stream] or
+/// [trap_stream_guard].
 pub struct SignalStream {
-    task: Arc<AtomicTask>,
+    waker: Arc<AtomicWaker>,
     recv: xchan::Receiver<Signal>,
 }
 
 impl Stream for SignalStream {
     type Item = Signal;
-    type Error = ();
 
-    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
-        self.task.register();
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.waker.register(cx.waker());
         match self.recv.try_recv() {
-            Ok(signal) => Ok(Async::Ready(Some(signal))),
-            Err(xchan::TryRecvError::Empty) => Ok(Async::NotReady),
-            Err(xchan::TryRecvError::Disconnected) => Ok(Async::Ready(None)),
+            Ok(signal) => Poll::Ready(Some(signal)),
+            Err(xchan::TryRecvError::Empty) => Poll::Pending,
+            Err(xchan::TryRecvError::Disconnected) => Poll::Ready(None),
         }
     }
 }
 
+fn new_signal_stream() -> (SignalStream, impl Fn(Signal) -> Response) {
+    let (send, recv) = xchan::bounded(1);
+    let waker = Arc::new(AtomicWaker::new());
+    let stream = SignalStream {
+        waker: waker.clone(),
+        recv,
+    };
+    let handler = move |signal| {
+        send.send(signal).unwrap();
+        waker.wake();
+        Response::Handled
+    };
+    (stream, handler)
+}
+
 /// Traps one or more [Signal]s into a [SignalStream]. During the
 /// execution of the body function, all signals specified will be yielded as
 /// items in the stream.
@@ -36,21 +52,25 @@ impl Stream for SignalStream {
 /// * `body` - A function which accepts a [SignalStream] that generates the
 /// specified signals in the order they are received.
 pub fn trap_stream<RT: Sized>(
-    signals: &'static [Signal],
+    signals: Vec<Signal>,
     body: impl FnOnce(SignalStream) -> RT,
 ) -> Result<RT, Error> {
-    let (send, recv) = xchan::bounded(1);
-    let task = Arc::new(AtomicTask::new());
-    let stream = SignalStream {
-        task: task.clone(),
-        recv,
-    };
-    trap(
-        signals,
-        move |signal| {
-            send.send(signal).unwrap();
-            task.notify();
-        },
-        move || body(stream),
-    )
+    let (stream, handler) = new_signal_stream();
+    trap(signals, handler, move || body(stream))
+}
+
+/// Traps one or more [Signal]s into a [SignalStream], without requiring the
+/// stream to be consumed inside a scoped `body`. The returned [TrapGuard]
+/// keeps the trap active for as long as it is held, so the stream can be
+/// handed off to a long-lived task (e.g. polled from a tokio runtime)
+/// instead of a synchronous closure; dropping the guard pops the trap.
+///
+/// # Arguments
+///
+/// * `signals` - A list of signals to trap for the lifetime of the returned
+/// [TrapGuard].
+pub fn trap_stream_guard(signals: Vec<Signal>) -> Result<(SignalStream, TrapGuard), Error> {
+    let (stream, handler) = new_signal_stream();
+    let trap = Trap::new(signals, Arc::new(handler))?;
+    Ok((stream, TrapGuard(trap)))
 }
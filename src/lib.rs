@@ -8,6 +8,7 @@
 //! wintrap::trap(vec![wintrap::Signal::CtrlC, wintrap::Signal::CloseWindow], |signal| {
 //!     // handle signal here
 //!     println!("Caught a signal: {:?}", signal);
+//!     wintrap::Response::Handled
 //! }, || {
 //!     // do work
 //!     println!("Doing work");
@@ -24,22 +25,28 @@
 #[macro_use]
 extern crate lazy_static;
 
+pub mod futures;
 mod windows;
 use crossbeam_channel;
-use std::collections::{HashMap, LinkedList};
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::Duration;
 use std::{error, fmt, process};
 use winapi::shared::minwindef::{BOOL, DWORD, FALSE, LPARAM, LRESULT, TRUE, UINT, WPARAM};
 use winapi::shared::windef::HWND;
-use winapi::um::wincon::{CTRL_BREAK_EVENT, CTRL_CLOSE_EVENT, CTRL_C_EVENT};
+use winapi::um::wincon::{
+    CTRL_BREAK_EVENT, CTRL_CLOSE_EVENT, CTRL_C_EVENT, CTRL_LOGOFF_EVENT, CTRL_SHUTDOWN_EVENT,
+};
 use winapi::um::winuser::{DefWindowProcW, WM_CLOSE, WM_QUIT};
 
 /// Associates one or more [Signal]s to an callback function to be executed in
 /// a dedicated thread while `body` is executing. A caveat of its usage is that
 /// *only one thread* is ever able to trap signals throughout the entire
 /// execution of your program. You are free to nest traps freely, however, only
-/// the innermost signal handlers will be executed.
+/// the innermost signal handlers will be executed — unless another caller
+/// shares the same [Signal] via [trap_all], in which case that broadcast
+/// handler also runs independently of nesting position; see [trap_all].
 ///
 /// # Arguments
 ///
@@ -47,20 +54,82 @@ use winapi::um::winuser::{DefWindowProcW, WM_CLOSE, WM_QUIT};
 ///
 /// * `handler` - The handler to execute whenever a signal is trapped. These
 /// signals will be trapped and handled in the order that they are received in
-/// a dedicated thread. The handler will *override* the default behavior of the
-/// signal, in which most cases, is to end the process.
+/// a dedicated thread. Returning [Response::Handled] from the handler
+/// *overrides* the default behavior of the signal, in which most cases, is
+/// to end the process; returning [Response::InvokeDefault] lets the default
+/// behavior run after the handler returns, e.g. so a handler can perform
+/// cleanup and then let the signal terminate the process normally.
 ///
 /// * `body` - The code to execute while the trap is active. The return value
 /// will be used as the `Ok` value of the result of the trap call.
 pub fn trap<RT: Sized>(
     signals: Vec<Signal>,
-    handler: impl Fn(Signal) + Send + Sync + 'static,
+    handler: impl Fn(Signal) -> Response + Send + Sync + 'static,
     body: impl FnOnce() -> RT,
 ) -> Result<RT, Error> {
     let _trap_guard = Trap::new(signals, Arc::new(handler))?;
     Ok(body())
 }
 
+/// Like [trap], but puts the handler into broadcast mode: it is invoked
+/// every time one of `signals` is received, independent of nesting position,
+/// alongside any other live broadcast handler for that signal and the plain
+/// [trap] handler that happens to be innermost (if any). This suits
+/// independent subsystems that each want to react to the same signal
+/// without knowing about one another, without taking away a plain [trap]
+/// caller's guarantee of being the sole handler invoked when it isn't
+/// sharing the signal with a broadcast handler.
+///
+/// Handlers run outermost (oldest registered) to innermost (newest
+/// registered). The handlers that run for a given signal are its broadcast
+/// handlers plus the innermost handler, if that one didn't itself opt into
+/// broadcast mode; the overall [Response] is [Response::InvokeDefault] if
+/// any of them asked for it. A `CloseWindow` signal is eligible to quit the
+/// process when either its handler list is empty, or the overall response
+/// from running its handlers is [Response::InvokeDefault].
+///
+/// # Arguments
+///
+/// * `signals` - A vec of signals to trap in broadcast mode during the
+/// execution of `body`.
+///
+/// * `handler` - The handler to execute whenever a signal is trapped. See
+/// [trap] for how its [Response] is used.
+///
+/// * `body` - The code to execute while the trap is active. The return value
+/// will be used as the `Ok` value of the result of the trap call.
+pub fn trap_all<RT: Sized>(
+    signals: Vec<Signal>,
+    handler: impl Fn(Signal) -> Response + Send + Sync + 'static,
+    body: impl FnOnce() -> RT,
+) -> Result<RT, Error> {
+    let _trap_guard = Trap::new_with_mode(signals, Arc::new(handler), true)?;
+    Ok(body())
+}
+
+/// Installs a trap for one or more [Signal]s that stays active for as long
+/// as the returned [TrapGuard] is held, instead of only for the duration of
+/// a scoped `body` closure. This suits long-running applications (servers,
+/// event loops) that want to install a trap once at startup and keep it in
+/// their app state for the remainder of the process, composing with e.g.
+/// [futures::trap_stream_guard] instead of having to nest everything in a
+/// closure. Dropping the guard pops the trap, the same way returning from
+/// [trap]'s `body` does.
+///
+/// # Arguments
+///
+/// * `signals` - A vec of signals to trap for the lifetime of the returned
+/// [TrapGuard].
+///
+/// * `handler` - The handler to execute whenever a signal is trapped. See
+/// [trap] for how its [Response] is used.
+pub fn install(
+    signals: Vec<Signal>,
+    handler: impl Fn(Signal) -> Response + Send + Sync + 'static,
+) -> Result<TrapGuard, Error> {
+    Ok(TrapGuard(Trap::new(signals, Arc::new(handler))?))
+}
+
 /// Represents one of several abstracted "signals" available to Windows
 /// processes. A number of these signals may be associated with a single [trap]
 /// call.
@@ -89,6 +158,18 @@ pub enum Signal {
     /// process, which is done by [std::process::Child::kill()] and the Windows
     /// command line tool `taskkill`, among others.
     CloseWindow,
+
+    /// `SetConsoleCtrlHandler`-generated `CTRL_LOGOFF_EVENT`. It is generated
+    /// when the user is logging off. Note that this signal is only ever
+    /// delivered to processes running as a service; it is not sent to
+    /// interactive console applications.
+    Logoff,
+
+    /// `SetConsoleCtrlHandler`-generated `CTRL_SHUTDOWN_EVENT`. It is
+    /// generated when the system is shutting down. Note that this signal is
+    /// only ever delivered to processes running as a service; it is not sent
+    /// to interactive console applications.
+    Shutdown,
 }
 
 impl Signal {
@@ -97,6 +178,8 @@ impl Signal {
             CTRL_C_EVENT => Some(Signal::CtrlC),
             CTRL_BREAK_EVENT => Some(Signal::CtrlBreak),
             CTRL_CLOSE_EVENT => Some(Signal::CloseConsole),
+            CTRL_LOGOFF_EVENT => Some(Signal::Logoff),
+            CTRL_SHUTDOWN_EVENT => Some(Signal::Shutdown),
             _ => None,
         }
     }
@@ -112,6 +195,23 @@ impl Signal {
     }
 }
 
+/// Returned by a [trap] handler to indicate whether the default OS behavior
+/// for the trapped [Signal] should also run once the handler returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Response {
+    /// Suppress the default behavior for the signal. This is the crate's
+    /// historical behavior: the process is left running and it is up to the
+    /// handler (and the surrounding `body`) to decide what happens next.
+    Handled,
+
+    /// Let the default behavior for the signal run after the handler
+    /// returns. For console events this means the next handler in line (or
+    /// the OS default, typically process termination) runs; for
+    /// `CloseWindow` it means the window is allowed to close as if no
+    /// handler were registered.
+    InvokeDefault,
+}
+
 /// An error that may potentially be generated by [trap]. These errors will
 /// rarely ever be produced, and you can unwrap `Result`s safely in most cases.
 #[derive(Debug)]
@@ -160,36 +260,77 @@ lazy_static! {
 
 struct Trap {
     signals: Vec<Signal>,
+    id: TrapHandlerId,
 }
 
 impl Trap {
     fn new(
         signals: Vec<Signal>,
-        handler: Arc<dyn Fn(Signal) + Send + Sync + 'static>,
+        handler: Arc<dyn Fn(Signal) -> Response + Send + Sync + 'static>,
+    ) -> Result<Self, Error> {
+        Self::new_with_mode(signals, handler, false)
+    }
+
+    fn new_with_mode(
+        signals: Vec<Signal>,
+        handler: Arc<dyn Fn(Signal) -> Response + Send + Sync + 'static>,
+        broadcast: bool,
     ) -> Result<Self, Error> {
         assert_eq!(*TRAP_OWNER_THREAD_ID, thread::current().id());
         let mut trap_stack = TRAP_STACK.lock().unwrap();
-        trap_stack.push_trap(signals.as_slice(), handler)?;
-        Ok(Trap { signals })
+        let id = trap_stack.push_trap(signals.as_slice(), handler, broadcast)?;
+        Ok(Trap { signals, id })
     }
 }
 
 impl Drop for Trap {
     fn drop(&mut self) {
         let mut trap_stack = TRAP_STACK.lock().unwrap();
-        trap_stack.pop_trap(self.signals.as_ref());
+        trap_stack.pop_trap(self.signals.as_ref(), self.id);
     }
 }
 
 impl !Send for Trap {}
 impl !Sync for Trap {}
 
-type TrapCallbacks = HashMap<Signal, LinkedList<Arc<dyn Fn(Signal) + Send + Sync + 'static>>>;
+/// An owning handle to an active trap, returned by [install] and
+/// [futures::trap_stream_guard]. Dropping a `TrapGuard` pops the trap, the
+/// same way leaving the scope of [trap]'s `body` does.
+///
+/// Unlike the scoped guard used internally by [trap], `TrapGuard` is `Send`
+/// and `Sync`, so it can be moved into another thread — e.g. stored in a
+/// future spawned onto a multi-threaded `tokio` runtime — and dropped
+/// there. This is sound because dropping a trap only pops it off the
+/// global, mutex-protected trap stack, which isn't tied to any particular
+/// thread; only *installing* a trap (via [trap], [trap_all], or [install]
+/// itself) is restricted to the process's single trap-owning thread, and
+/// that restriction is enforced before a `TrapGuard` is ever handed out.
+pub struct TrapGuard(Trap);
+
+unsafe impl Send for TrapGuard {}
+unsafe impl Sync for TrapGuard {}
+
+/// A stable identity for a registered [TrapHandler], assigned by
+/// [TrapStack::push_trap] and handed back to [TrapStack::pop_trap]. This
+/// lets a [Trap] remove its own entry regardless of push/pop order, which
+/// matters once guards can be dropped out of LIFO order (see [install]).
+type TrapHandlerId = u64;
+
+/// A single registered handler for a [Signal], along with whether it opted
+/// into [trap_all]'s broadcast dispatch.
+struct TrapHandler {
+    id: TrapHandlerId,
+    callback: Arc<dyn Fn(Signal) -> Response + Send + Sync + 'static>,
+    broadcast: bool,
+}
+
+type TrapCallbacks = HashMap<Signal, Vec<TrapHandler>>;
 
 struct TrapStack {
     num_traps: usize,
     trap_thread_data: Option<TrapThreadData>,
     callbacks: TrapCallbacks,
+    next_handler_id: TrapHandlerId,
 }
 
 impl TrapStack {
@@ -198,6 +339,7 @@ impl TrapStack {
             num_traps: 0,
             trap_thread_data: None,
             callbacks: HashMap::new(),
+            next_handler_id: 0,
         }
     }
 
@@ -221,23 +363,36 @@ impl TrapStack {
     fn push_trap(
         &mut self,
         signals: &[Signal],
-        handler: Arc<dyn Fn(Signal) + Send + Sync + 'static>,
-    ) -> Result<(), Error> {
+        handler: Arc<dyn Fn(Signal) -> Response + Send + Sync + 'static>,
+        broadcast: bool,
+    ) -> Result<TrapHandlerId, Error> {
         self.increment_trap_count()?;
+        let id = self.next_handler_id;
+        self.next_handler_id += 1;
         for signal in signals.iter() {
             self.callbacks
                 .entry(*signal)
-                .or_insert_with(LinkedList::new)
-                .push_back(handler.clone());
+                .or_insert_with(Vec::new)
+                .push(TrapHandler {
+                    id,
+                    callback: handler.clone(),
+                    broadcast,
+                });
         }
-        Ok(())
+        Ok(id)
     }
 
-    fn pop_trap(&mut self, signals: &[Signal]) {
+    fn pop_trap(&mut self, signals: &[Signal], id: TrapHandlerId) {
         self.decrement_trap_count();
         for signal in signals.iter() {
             let callbacks = self.callbacks.get_mut(signal).unwrap();
-            callbacks.pop_back().unwrap();
+            let len_before = callbacks.len();
+            callbacks.retain(|handler| handler.id != id);
+            assert_eq!(
+                callbacks.len(),
+                len_before - 1,
+                "exactly one handler with this id should have been registered for this signal"
+            );
             if callbacks.is_empty() {
                 self.callbacks.remove(signal);
             }
@@ -248,6 +403,34 @@ impl TrapStack {
         self.callbacks.contains_key(&signal)
     }
 
+    /// Invokes the handler(s) registered for `signal`, if any. Every handler
+    /// that opted into broadcast mode (via [trap_all]) is invoked, in
+    /// outermost (oldest) to innermost (newest) order, regardless of
+    /// nesting. The innermost (most recently registered) handler is invoked
+    /// on top of that if it did not itself opt into broadcast mode, so a
+    /// plain [trap] caller keeps its documented guarantee of being the only
+    /// handler that runs for signals it doesn't share with a broadcast
+    /// handler. When multiple handlers run, the result is
+    /// [Response::InvokeDefault] if any of them asked for it.
+    fn dispatch(&self, signal: Signal) -> Option<Response> {
+        let callback_list = self.callbacks.get(&signal)?;
+        let mut responses: Vec<Response> = callback_list
+            .iter()
+            .filter(|handler| handler.broadcast)
+            .map(|handler| (handler.callback)(signal))
+            .collect();
+        let innermost = callback_list.last().unwrap();
+        if !innermost.broadcast {
+            responses.push((innermost.callback)(signal));
+        }
+        let response = if responses.contains(&Response::InvokeDefault) {
+            Response::InvokeDefault
+        } else {
+            Response::Handled
+        };
+        Some(response)
+    }
+
     fn exit_if_only_window(&self) {
         if let Some(ref trap_thread_data) = self.trap_thread_data {
             // If we get a WM_CLOSE event and we don't have a handler for it, AND if
@@ -311,11 +494,32 @@ impl TrapThreadData {
                         Signal::from_window_message(msg.message, msg.wParam, msg.lParam)
                     {
                         let trap_stack = TRAP_STACK.lock().unwrap();
-                        if let Some(callback_list) = trap_stack.callbacks.get(&signal) {
-                            callback_list.back().unwrap()(signal);
+                        let response = trap_stack.dispatch(signal);
+                        if msg.message == *WM_CONSOLE_CTRL {
+                            // Reclaim the one-shot reply channel
+                            // enqueue_ctrl_event stashed in lParam for this
+                            // specific event, and tell console_ctrl_handler
+                            // whether to invoke the default behavior. Windows
+                            // spawns a new thread per console-control-handler
+                            // invocation, so concurrent events each carry
+                            // their own channel rather than sharing one.
+                            let response_tx = unsafe {
+                                Box::from_raw(
+                                    msg.lParam as *mut crossbeam_channel::Sender<Response>
+                                )
+                            };
+                            let _ = response_tx.send(response.unwrap_or(Response::InvokeDefault));
                         } else if msg.message == WM_CLOSE {
-                            // Exit the process if we don't own any other windows.
-                            trap_stack.exit_if_only_window();
+                            match response {
+                                Some(Response::Handled) => {}
+                                Some(Response::InvokeDefault) | None => {
+                                    // Either no handler is registered, or the
+                                    // handler asked for the default behavior;
+                                    // in both cases, fall through as if no
+                                    // handler were registered.
+                                    trap_stack.exit_if_only_window();
+                                }
+                            }
                         }
                     }
                 })
@@ -330,8 +534,26 @@ impl TrapThreadData {
         })
     }
 
-    fn enqueue_ctrl_event(&self, event: DWORD) -> Result<(), DWORD> {
-        windows::post_message(self.window_handle, *WM_CONSOLE_CTRL, event as WPARAM, 0)
+    fn enqueue_ctrl_event(
+        &self,
+        event: DWORD,
+    ) -> Result<crossbeam_channel::Receiver<Response>, DWORD> {
+        let (response_tx, response_rx) = crossbeam_channel::bounded(1);
+        let response_tx = Box::into_raw(Box::new(response_tx));
+        match windows::post_message(
+            self.window_handle,
+            *WM_CONSOLE_CTRL,
+            event as WPARAM,
+            response_tx as LPARAM,
+        ) {
+            Ok(()) => Ok(response_rx),
+            Err(code) => {
+                // The window thread will never see this message, so reclaim
+                // the boxed sender ourselves instead of leaking it.
+                drop(unsafe { Box::from_raw(response_tx) });
+                Err(code)
+            }
+        }
     }
 }
 
@@ -343,24 +565,48 @@ impl Drop for TrapThreadData {
     }
 }
 
+/// How long [console_ctrl_handler] will wait for the window thread's reply
+/// before giving up and invoking the default behavior itself. If the window
+/// thread is torn down (e.g. the last trap is dropped) while this event's
+/// `WM_CONSOLE_CTRL` message is still queued, the event loop can exit
+/// without ever reclaiming and replying on this event's channel, which would
+/// otherwise block this thread forever. Windows only waits a few seconds
+/// before treating a console-control handler as unresponsive anyway, so
+/// waiting any longer than that buys nothing.
+const CONSOLE_CTRL_REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
 unsafe extern "system" fn console_ctrl_handler(event: DWORD) -> BOOL {
     match Signal::from_console_ctrl_event(event) {
         Some(signal) => {
-            let trap_stack = TRAP_STACK.lock().unwrap();
-            if trap_stack.has_handler_for(signal) {
-                // A handler exists, so queue the signal to be handled in the
-                // window thread
+            // Queue the signal to be handled in the window thread, then drop
+            // the lock before waiting for its response below: the window
+            // thread needs to take the same lock to look up the callback.
+            // Windows invokes this handler on a fresh thread for every
+            // event, so `enqueue_ctrl_event` hands back a reply channel
+            // scoped to this specific call; it is never shared with a
+            // concurrent invocation handling a different event.
+            let response_rx = {
+                let trap_stack = TRAP_STACK.lock().unwrap();
+                if !trap_stack.has_handler_for(signal) {
+                    return FALSE;
+                }
                 match trap_stack.trap_thread_data {
-                    Some(ref trap_thread_data) => {
-                        match trap_thread_data.enqueue_ctrl_event(event) {
-                            Ok(_) => TRUE,
-                            Err(_) => FALSE,
-                        }
-                    }
-                    None => FALSE,
+                    Some(ref trap_thread_data) => match trap_thread_data.enqueue_ctrl_event(event)
+                    {
+                        Ok(response_rx) => response_rx,
+                        Err(_) => return FALSE,
+                    },
+                    None => return FALSE,
                 }
-            } else {
-                FALSE
+            };
+            // Let the handler decide whether the default behavior (which,
+            // for most console events, is to end the process) should run.
+            // Bound the wait: if the window thread tears down before it
+            // reaches this event's message (see CONSOLE_CTRL_REPLY_TIMEOUT),
+            // the reply never comes and recv() alone would block forever.
+            match response_rx.recv_timeout(CONSOLE_CTRL_REPLY_TIMEOUT) {
+                Ok(Response::Handled) => TRUE,
+                Ok(Response::InvokeDefault) | Err(_) => FALSE,
             }
         }
         None => FALSE,
@@ -390,17 +636,18 @@ unsafe extern "system" fn window_proc(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
     #[test]
     fn test_nested_traps() {
         trap(
             vec![Signal::CtrlC, Signal::CloseWindow],
-            |_| {},
+            |_| Response::Handled,
             || {
                 println!("Trap 1");
                 trap(
                     vec![Signal::CtrlC, Signal::CtrlBreak],
-                    |_| {},
+                    |_| Response::Handled,
                     || {
                         println!("Trap 2");
                     },
@@ -415,7 +662,7 @@ mod tests {
     fn test_trap_exit_and_reenter() {
         trap(
             vec![Signal::CtrlC],
-            |_| {},
+            |_| Response::Handled,
             || {
                 println!("Trap 1");
             },
@@ -423,11 +670,139 @@ mod tests {
         .unwrap();
         trap(
             vec![Signal::CtrlC],
-            |_| {},
+            |_| Response::Handled,
             || {
                 println!("Trap 2");
             },
         )
         .unwrap();
     }
+
+    #[test]
+    fn test_install_and_drop_guard() {
+        let guard = install(vec![Signal::CtrlC], |_| Response::Handled).unwrap();
+        println!("Trap installed");
+        drop(guard);
+    }
+
+    fn counting_handler(
+        id: TrapHandlerId,
+        calls: Arc<AtomicUsize>,
+        broadcast: bool,
+    ) -> TrapHandler {
+        TrapHandler {
+            id,
+            callback: Arc::new(move |_| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Response::Handled
+            }),
+            broadcast,
+        }
+    }
+
+    fn trap_stack_with(signal: Signal, handlers: Vec<TrapHandler>) -> TrapStack {
+        let next_handler_id = handlers
+            .iter()
+            .map(|handler| handler.id)
+            .max()
+            .map_or(0, |id| id + 1);
+        let mut callbacks: TrapCallbacks = HashMap::new();
+        callbacks.insert(signal, handlers.into_iter().collect());
+        TrapStack {
+            num_traps: 0,
+            trap_thread_data: None,
+            callbacks,
+            next_handler_id,
+        }
+    }
+
+    #[test]
+    fn test_dispatch_plain_handlers_only_invoke_innermost() {
+        let outer_calls = Arc::new(AtomicUsize::new(0));
+        let inner_calls = Arc::new(AtomicUsize::new(0));
+        let trap_stack = trap_stack_with(
+            Signal::CtrlC,
+            vec![
+                counting_handler(0, outer_calls.clone(), false),
+                counting_handler(1, inner_calls.clone(), false),
+            ],
+        );
+
+        trap_stack.dispatch(Signal::CtrlC);
+
+        assert_eq!(outer_calls.load(Ordering::SeqCst), 0);
+        assert_eq!(inner_calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_dispatch_broadcasts_to_every_opted_in_handler() {
+        let outer_calls = Arc::new(AtomicUsize::new(0));
+        let inner_calls = Arc::new(AtomicUsize::new(0));
+        let trap_stack = trap_stack_with(
+            Signal::CtrlC,
+            vec![
+                counting_handler(0, outer_calls.clone(), true),
+                counting_handler(1, inner_calls.clone(), true),
+            ],
+        );
+
+        trap_stack.dispatch(Signal::CtrlC);
+
+        assert_eq!(outer_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(inner_calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_dispatch_broadcast_handler_does_not_suppress_plain_innermost() {
+        let outer_calls = Arc::new(AtomicUsize::new(0));
+        let inner_calls = Arc::new(AtomicUsize::new(0));
+        let trap_stack = trap_stack_with(
+            Signal::CtrlC,
+            vec![
+                counting_handler(0, outer_calls.clone(), true),
+                counting_handler(1, inner_calls.clone(), false),
+            ],
+        );
+
+        trap_stack.dispatch(Signal::CtrlC);
+
+        assert_eq!(outer_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(inner_calls.load(Ordering::SeqCst), 1);
+        assert!(trap_stack.dispatch(Signal::CtrlBreak).is_none());
+    }
+
+    #[test]
+    fn test_pop_trap_removes_handler_by_id_out_of_order() {
+        // Simulates two overlapping-signal TrapGuards whose drop order is the
+        // reverse of a strict LIFO stack: the first one pushed (h1) is popped
+        // first, while the second one pushed (h2) is still held. pop_trap
+        // must remove h1's own entry, not whatever happens to be last in the
+        // list.
+        let h1_calls = Arc::new(AtomicUsize::new(0));
+        let h2_calls = Arc::new(AtomicUsize::new(0));
+        let mut trap_stack = TrapStack::new();
+        let h1 = Arc::new({
+            let h1_calls = h1_calls.clone();
+            move |_| {
+                h1_calls.fetch_add(1, Ordering::SeqCst);
+                Response::Handled
+            }
+        });
+        let h2 = Arc::new({
+            let h2_calls = h2_calls.clone();
+            move |_| {
+                h2_calls.fetch_add(1, Ordering::SeqCst);
+                Response::Handled
+            }
+        });
+        let signals = [Signal::CtrlC];
+        let id1 = trap_stack.push_trap(&signals, h1, false).unwrap();
+        let _id2 = trap_stack.push_trap(&signals, h2, false).unwrap();
+
+        trap_stack.pop_trap(&signals, id1);
+
+        trap_stack.dispatch(Signal::CtrlC);
+        assert_eq!(h1_calls.load(Ordering::SeqCst), 0);
+        assert_eq!(h2_calls.load(Ordering::SeqCst), 1);
+    }
 }